@@ -0,0 +1,221 @@
+use rand::RngCore;
+use reqwest::RequestBuilder;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+use tauri::AppHandle;
+use tauri_plugin_shell::ShellExt;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::time::timeout;
+
+/// How long to wait for the user to complete sign-in in the system browser
+/// before giving up on an abandoned flow.
+const REDIRECT_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// How an outbound request to the chat endpoint authenticates itself.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub(crate) enum AuthMode {
+    FunctionKey { key: String },
+    BearerToken { access_token: String },
+}
+
+impl AuthMode {
+    /// Attach the appropriate auth header to an in-flight request.
+    pub(crate) fn apply(&self, builder: RequestBuilder) -> RequestBuilder {
+        match self {
+            AuthMode::FunctionKey { key } if !key.is_empty() => {
+                builder.header("x-functions-key", key)
+            }
+            AuthMode::FunctionKey { .. } => builder,
+            AuthMode::BearerToken { access_token } => {
+                builder.header("Authorization", format!("Bearer {}", access_token))
+            }
+        }
+    }
+}
+
+/// Which kind of credential a session authenticates with, without the
+/// credential value itself. A `Session` persists only this discriminant
+/// plus a `secret_ref`; the literal key/token is resolved just-in-time from
+/// the encrypted secret store and never written to disk.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub(crate) enum AuthScheme {
+    FunctionKey,
+    BearerToken,
+}
+
+impl AuthScheme {
+    /// Pair a resolved secret value with this scheme to build the live
+    /// `AuthMode` a request actually sends.
+    pub(crate) fn resolve(self, secret_value: String) -> AuthMode {
+        match self {
+            AuthScheme::FunctionKey => AuthMode::FunctionKey { key: secret_value },
+            AuthScheme::BearerToken => AuthMode::BearerToken {
+                access_token: secret_value,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct AcquiredToken {
+    access_token: String,
+    expires_in: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: u64,
+}
+
+fn base64_url_no_pad(bytes: &[u8]) -> String {
+    use base64::Engine;
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// Generate a PKCE code verifier/challenge pair (RFC 7636, S256 method).
+fn generate_pkce_pair() -> (String, String) {
+    let mut verifier_bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut verifier_bytes);
+    let verifier = base64_url_no_pad(&verifier_bytes);
+
+    let mut hasher = Sha256::new();
+    hasher.update(verifier.as_bytes());
+    let challenge = base64_url_no_pad(&hasher.finalize());
+
+    (verifier, challenge)
+}
+
+/// Perform an OAuth2 authorization-code-with-PKCE flow against an Entra ID
+/// tenant: open the system browser to the authorize endpoint, listen on a
+/// loopback port for the redirect, then exchange the code for a token.
+#[tauri::command]
+pub(crate) async fn acquire_access_token(
+    app: AppHandle,
+    tenant_id: String,
+    client_id: String,
+    scope: String,
+) -> Result<AcquiredToken, String> {
+    let authority = format!("https://login.microsoftonline.com/{}", tenant_id);
+    let (verifier, challenge) = generate_pkce_pair();
+
+    let mut csrf_state_bytes = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut csrf_state_bytes);
+    let csrf_state = base64_url_no_pad(&csrf_state_bytes);
+
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .map_err(|e| format!("Failed to open redirect listener: {}", e))?;
+    let redirect_port = listener
+        .local_addr()
+        .map_err(|e| e.to_string())?
+        .port();
+    let redirect_uri = format!("http://127.0.0.1:{}/callback", redirect_port);
+
+    let authorize_url = format!(
+        "{authority}/oauth2/v2.0/authorize?client_id={client_id}&response_type=code&redirect_uri={redirect}&scope={scope}&state={state}&code_challenge={challenge}&code_challenge_method=S256",
+        authority = authority,
+        client_id = client_id,
+        redirect = urlencoding::encode(&redirect_uri),
+        scope = urlencoding::encode(&scope),
+        state = urlencoding::encode(&csrf_state),
+        challenge = challenge,
+    );
+
+    app.shell()
+        .open(&authorize_url, None)
+        .map_err(|e| format!("Failed to open system browser: {}", e))?;
+
+    let code = match timeout(REDIRECT_TIMEOUT, wait_for_redirect_code(listener, csrf_state)).await
+    {
+        Ok(result) => result?,
+        Err(_) => return Err("Timed out waiting for the sign-in redirect".to_string()),
+    };
+
+    let token_url = format!("{}/oauth2/v2.0/token", authority);
+    let params = [
+        ("client_id", client_id.as_str()),
+        ("grant_type", "authorization_code"),
+        ("code", code.as_str()),
+        ("redirect_uri", redirect_uri.as_str()),
+        ("code_verifier", verifier.as_str()),
+        ("scope", scope.as_str()),
+    ];
+
+    let response = reqwest::Client::new()
+        .post(&token_url)
+        .form(&params)
+        .send()
+        .await
+        .map_err(|e| format!("Token exchange failed: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Token exchange error: HTTP {}", response.status()));
+    }
+
+    let token = response
+        .json::<TokenResponse>()
+        .await
+        .map_err(|e| format!("Failed to parse token response: {}", e))?;
+
+    Ok(AcquiredToken {
+        access_token: token.access_token,
+        expires_in: token.expires_in,
+    })
+}
+
+/// Accept a single loopback connection carrying the `code=...` redirect from
+/// the authorize endpoint, verify its `state` matches the one we sent (CSRF
+/// protection), and reply with a short confirmation page.
+async fn wait_for_redirect_code(
+    listener: TcpListener,
+    expected_state: String,
+) -> Result<String, String> {
+    let (mut stream, _) = listener
+        .accept()
+        .await
+        .map_err(|e| format!("Failed to accept redirect: {}", e))?;
+
+    let mut buf = [0u8; 4096];
+    let n = stream
+        .read(&mut buf)
+        .await
+        .map_err(|e| format!("Failed to read redirect: {}", e))?;
+    let request_line = String::from_utf8_lossy(&buf[..n]);
+    let path = request_line
+        .lines()
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .ok_or("Malformed redirect request")?;
+
+    let query = path.split_once('?').map(|(_, query)| query).unwrap_or("");
+
+    let returned_state = query
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("state="))
+        .ok_or("Redirect did not include a state parameter")?;
+
+    if returned_state != expected_state {
+        return Err("Redirect state did not match the request (possible CSRF)".to_string());
+    }
+
+    let code = query
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("code="))
+        .ok_or("Redirect did not include an authorization code")?
+        .to_string();
+
+    let body = "<html><body>Sign-in complete, you can close this window.</body></html>";
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: {}\r\n\r\n{}",
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+
+    Ok(code)
+}
@@ -0,0 +1,100 @@
+//! Companion CLI that talks to the running Entra Copilot Agent app over its
+//! local IPC socket, so scripts and terminals can query the agent without
+//! going through the GUI.
+
+#[cfg(unix)]
+use serde::{Deserialize, Serialize};
+use std::env;
+#[cfg(unix)]
+use std::io::{BufRead, BufReader, Write};
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+#[cfg(unix)]
+use std::path::PathBuf;
+
+#[cfg(unix)]
+const APP_IDENTIFIER: &str = "com.kody-w.entra-copilot-agent";
+
+#[cfg(unix)]
+#[derive(Debug, Serialize)]
+struct CliRequest {
+    token: String,
+    user_guid: String,
+    user_input: String,
+}
+
+#[cfg(unix)]
+#[derive(Debug, Deserialize)]
+struct CliResponse {
+    ok: bool,
+    response: Option<serde_json::Value>,
+    error: Option<String>,
+}
+
+#[cfg(unix)]
+fn app_data_dir() -> PathBuf {
+    dirs::data_dir()
+        .expect("could not resolve the OS data directory")
+        .join(APP_IDENTIFIER)
+}
+
+#[cfg(not(unix))]
+fn main() {
+    eprintln!("entra_cli is not yet supported on this platform");
+    std::process::exit(1);
+}
+
+#[cfg(unix)]
+fn main() {
+    let mut args = env::args().skip(1);
+    let user_guid = args.next().unwrap_or_else(|| usage());
+    let user_input = args.collect::<Vec<_>>().join(" ");
+    if user_input.is_empty() {
+        usage();
+    }
+
+    let dir = app_data_dir();
+
+    let token = std::fs::read_to_string(dir.join("entra-cli.token")).unwrap_or_else(|e| {
+        eprintln!("Could not read IPC token (is the app running?): {}", e);
+        std::process::exit(1);
+    });
+
+    let request = CliRequest {
+        token,
+        user_guid,
+        user_input,
+    };
+
+    let mut stream = UnixStream::connect(dir.join("entra-cli.sock")).unwrap_or_else(|e| {
+        eprintln!("Could not connect to the running app: {}", e);
+        std::process::exit(1);
+    });
+
+    let mut body = serde_json::to_string(&request).expect("failed to encode request");
+    body.push('\n');
+    stream
+        .write_all(body.as_bytes())
+        .expect("failed to send request");
+
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader
+        .read_line(&mut line)
+        .expect("failed to read response");
+
+    let response: CliResponse = serde_json::from_str(&line).expect("failed to parse response");
+    if response.ok {
+        if let Some(value) = response.response {
+            println!("{}", serde_json::to_string_pretty(&value).unwrap());
+        }
+    } else {
+        eprintln!("Error: {}", response.error.unwrap_or_default());
+        std::process::exit(1);
+    }
+}
+
+fn usage() -> ! {
+    eprintln!("usage: entra_cli <user_guid> <message>");
+    std::process::exit(1);
+}
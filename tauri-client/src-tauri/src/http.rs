@@ -0,0 +1,128 @@
+use reqwest::{Client, Response, StatusCode};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::sleep;
+use tokio_util::sync::CancellationToken;
+
+const MAX_RETRIES: u32 = 3;
+const BASE_BACKOFF_MS: u64 = 250;
+
+/// The single shared client that chat traffic (`send_chat_message` and the
+/// streaming command) goes through, so connections are pooled instead of
+/// reconnecting on every turn.
+pub(crate) type HttpClientState = Client;
+
+/// Cancellation tokens for in-flight or streaming turns, keyed by the
+/// caller-supplied `request_id`, so the UI can abort a turn mid-flight.
+pub(crate) type CancellationRegistry = Mutex<HashMap<String, CancellationToken>>;
+
+/// Build the shared client: pooled connections plus connect/read timeouts
+/// so a single network blip can't hang a turn forever.
+pub(crate) fn build_client() -> Client {
+    Client::builder()
+        .connect_timeout(Duration::from_secs(10))
+        .read_timeout(Duration::from_secs(30))
+        .pool_idle_timeout(Duration::from_secs(90))
+        .pool_max_idle_per_host(4)
+        .build()
+        .expect("failed to build shared HTTP client")
+}
+
+/// Register a fresh cancellation token for `request_id`, replacing any
+/// stale entry left over from a previous turn with the same id.
+pub(crate) fn register(
+    registry: &CancellationRegistry,
+    request_id: &str,
+) -> Result<CancellationToken, String> {
+    let token = CancellationToken::new();
+    registry
+        .lock()
+        .map_err(|e| e.to_string())?
+        .insert(request_id.to_string(), token.clone());
+    Ok(token)
+}
+
+/// Drop the cancellation token once a turn has finished, successfully or
+/// not, so the map doesn't grow unbounded.
+pub(crate) fn unregister(registry: &CancellationRegistry, request_id: &str) -> Result<(), String> {
+    registry
+        .lock()
+        .map_err(|e| e.to_string())?
+        .remove(request_id);
+    Ok(())
+}
+
+/// Cancel an in-flight or streaming turn by its `request_id`.
+#[tauri::command]
+pub(crate) fn cancel_request(
+    registry: tauri::State<'_, CancellationRegistry>,
+    request_id: String,
+) -> Result<(), String> {
+    let registry = registry.lock().map_err(|e| e.to_string())?;
+    if let Some(token) = registry.get(&request_id) {
+        token.cancel();
+    }
+    Ok(())
+}
+
+/// Send the request built by `build_request`, retrying with exponential
+/// backoff on HTTP 429/503 (honoring `Retry-After`) and on transport
+/// errors, up to `MAX_RETRIES` attempts. `on_retry(attempt, delay)` fires
+/// before each retry so callers can surface progress to the frontend.
+/// Honors `cancellation` both while a request is in flight and during the
+/// backoff sleep.
+pub(crate) async fn send_with_retry(
+    build_request: impl Fn() -> reqwest::RequestBuilder,
+    cancellation: &CancellationToken,
+    mut on_retry: impl FnMut(u32, Duration),
+) -> Result<Response, String> {
+    let mut attempt = 0;
+
+    loop {
+        let outcome = tokio::select! {
+            _ = cancellation.cancelled() => return Err("Request cancelled".to_string()),
+            outcome = build_request().send() => outcome,
+        };
+
+        let retry_after = match &outcome {
+            Ok(response) if is_retryable(response.status()) && attempt < MAX_RETRIES => {
+                Some(retry_delay(response, attempt))
+            }
+            Err(_) if attempt < MAX_RETRIES => Some(backoff_delay(attempt)),
+            _ => None,
+        };
+
+        match retry_after {
+            Some(delay) => {
+                attempt += 1;
+                on_retry(attempt, delay);
+                tokio::select! {
+                    _ = cancellation.cancelled() => return Err("Request cancelled".to_string()),
+                    _ = sleep(delay) => {}
+                }
+            }
+            None => {
+                return outcome.map_err(|e| format!("Network error: {}", e));
+            }
+        }
+    }
+}
+
+fn is_retryable(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status == StatusCode::SERVICE_UNAVAILABLE
+}
+
+fn retry_delay(response: &Response, attempt: u32) -> Duration {
+    response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok())
+        .map(Duration::from_secs)
+        .unwrap_or_else(|| backoff_delay(attempt))
+}
+
+fn backoff_delay(attempt: u32) -> Duration {
+    Duration::from_millis(BASE_BACKOFF_MS * 2u64.pow(attempt))
+}
@@ -0,0 +1,168 @@
+#[cfg(unix)]
+use crate::http::{CancellationRegistry, HttpClientState};
+#[cfg(unix)]
+use crate::state::ManagedState;
+#[cfg(unix)]
+use crate::{send_chat_message_core, ApiResponse};
+use base64::Engine;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+#[cfg(unix)]
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+#[cfg(unix)]
+use tokio::net::{UnixListener, UnixStream};
+
+#[cfg(unix)]
+#[derive(Debug, Deserialize)]
+struct CliRequest {
+    token: String,
+    user_guid: String,
+    user_input: String,
+}
+
+#[cfg(unix)]
+#[derive(Debug, Serialize)]
+struct CliResponse {
+    ok: bool,
+    response: Option<ApiResponse>,
+    error: Option<String>,
+}
+
+fn socket_path(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("entra-cli.sock"))
+}
+
+fn token_path(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("entra-cli.token"))
+}
+
+/// Restrict a just-created file to the owning user only, so another local
+/// account can't read the per-launch token or connect to the socket.
+#[cfg(unix)]
+fn restrict_permissions(path: &std::path::Path, mode: u32) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode))
+        .map_err(|e| format!("Failed to restrict permissions on {}: {}", path.display(), e))
+}
+
+/// Remove the socket and token files so a future launch starts clean.
+pub(crate) fn cleanup(app: &AppHandle) {
+    if let Ok(path) = socket_path(app) {
+        let _ = std::fs::remove_file(path);
+    }
+    if let Ok(path) = token_path(app) {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+/// Start the background IPC server `entra_cli` talks to: a Unix domain
+/// socket that accepts newline-delimited JSON requests and forwards them
+/// through the same handler `send_chat_message` uses. A fresh per-launch
+/// token is written to the app-data dir; callers must echo it back.
+#[cfg(unix)]
+pub(crate) fn start(app: AppHandle) -> Result<(), String> {
+    let socket_path = socket_path(&app)?;
+    let _ = std::fs::remove_file(&socket_path);
+
+    let mut token_bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut token_bytes);
+    let token = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(token_bytes);
+    let token_path = token_path(&app)?;
+    std::fs::write(&token_path, &token).map_err(|e| e.to_string())?;
+    restrict_permissions(&token_path, 0o600)?;
+
+    let listener = UnixListener::bind(&socket_path)
+        .map_err(|e| format!("Failed to bind IPC socket: {}", e))?;
+    restrict_permissions(&socket_path, 0o700)?;
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    let app = app.clone();
+                    let token = token.clone();
+                    tauri::async_runtime::spawn(handle_connection(app, token, stream));
+                }
+                Err(e) => {
+                    eprintln!("entra_cli IPC server stopped accepting connections: {}", e);
+                    break;
+                }
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// The companion CLI only speaks Unix domain sockets; on other platforms
+/// `start` returns an error instead of failing the build, so the app still
+/// starts (the caller logs the error) and no IPC server runs.
+#[cfg(not(unix))]
+pub(crate) fn start(_app: AppHandle) -> Result<(), String> {
+    Err("the entra_cli IPC server is not yet supported on this platform".to_string())
+}
+
+#[cfg(unix)]
+async fn handle_connection(app: AppHandle, expected_token: String, stream: UnixStream) {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+
+    let Ok(Some(line)) = lines.next_line().await else {
+        return;
+    };
+
+    let response = match serde_json::from_str::<CliRequest>(&line) {
+        Ok(request) if request.token == expected_token => {
+            let state = app.state::<ManagedState>();
+            let client = app.state::<HttpClientState>();
+            let cancellations = app.state::<CancellationRegistry>();
+            match send_chat_message_core(
+                &app,
+                &state,
+                &client,
+                &cancellations,
+                request.user_guid,
+                request.user_input,
+                None,
+            )
+            .await
+            {
+                Ok(response) => CliResponse {
+                    ok: true,
+                    response: Some(response),
+                    error: None,
+                },
+                Err(e) => CliResponse {
+                    ok: false,
+                    response: None,
+                    error: Some(e),
+                },
+            }
+        }
+        Ok(_) => CliResponse {
+            ok: false,
+            response: None,
+            error: Some("Invalid token".to_string()),
+        },
+        Err(e) => CliResponse {
+            ok: false,
+            response: None,
+            error: Some(format!("Malformed request: {}", e)),
+        },
+    };
+
+    if let Ok(mut body) = serde_json::to_string(&response) {
+        body.push('\n');
+        let _ = writer.write_all(body.as_bytes()).await;
+    }
+}
@@ -7,17 +7,30 @@
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
-use tauri::{Manager, AppHandle};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, State};
 
-#[derive(Debug, Serialize, Deserialize)]
-struct ChatMessage {
+mod auth;
+mod http;
+mod ipc;
+mod secrets;
+mod state;
+mod streaming;
+mod updater;
+
+use auth::{AuthMode, AuthScheme};
+use http::{CancellationRegistry, HttpClientState};
+use state::{AppState, ManagedState};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct ChatMessage {
     role: String,
     content: String,
     timestamp: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct ApiRequest {
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct ApiRequest {
     user_input: String,
     conversation_history: Vec<ChatMessage>,
     user_guid: Option<String>,
@@ -52,52 +65,134 @@ async fn import_data(path: String) -> Result<String, String> {
     fs::read_to_string(&path).map_err(|e| format!("Failed to import data: {}", e))
 }
 
-/// Send a chat message to the API endpoint with native HTTP client
+/// Send a chat message to the API endpoint, pulling endpoint config and
+/// conversation history from the managed session rather than the frontend.
 #[tauri::command]
 async fn send_chat_message(
-    endpoint_url: String,
-    api_key: Option<String>,
+    app: AppHandle,
+    state: State<'_, ManagedState>,
+    client: State<'_, HttpClientState>,
+    cancellations: State<'_, CancellationRegistry>,
+    user_guid: String,
     user_input: String,
-    conversation_history: Vec<ChatMessage>,
-    user_guid: Option<String>,
+    request_id: Option<String>,
+) -> Result<ApiResponse, String> {
+    send_chat_message_core(
+        &app,
+        &state,
+        &client,
+        &cancellations,
+        user_guid,
+        user_input,
+        request_id,
+    )
+    .await
+}
+
+/// Shared implementation behind `send_chat_message`, reusable by anything
+/// else that holds an `AppHandle` and managed state, such as the local IPC
+/// server.
+pub(crate) async fn send_chat_message_core(
+    app: &AppHandle,
+    state: &State<'_, ManagedState>,
+    client: &HttpClientState,
+    cancellations: &CancellationRegistry,
+    user_guid: String,
+    user_input: String,
+    request_id: Option<String>,
 ) -> Result<ApiResponse, String> {
-    let client = reqwest::Client::new();
+    let session = {
+        let state = state.lock().map_err(|e| e.to_string())?;
+        state
+            .get(&user_guid)
+            .ok_or_else(|| format!("No session for {}", user_guid))?
+    };
 
     let request = ApiRequest {
-        user_input,
-        conversation_history,
-        user_guid,
+        user_input: user_input.clone(),
+        conversation_history: session.messages,
+        user_guid: Some(user_guid.clone()),
     };
 
-    let mut request_builder = client
-        .post(&endpoint_url)
-        .header("Content-Type", "application/json")
-        .json(&request);
+    let auth_mode = resolve_session_auth(app, session.auth_scheme, session.secret_ref)?;
+    let endpoint_url = session.endpoint_url;
 
-    if let Some(key) = api_key {
-        if !key.is_empty() {
-            request_builder = request_builder.header("x-functions-key", key);
+    let build_request = || {
+        let mut builder = client
+            .post(&endpoint_url)
+            .header("Content-Type", "application/json")
+            .json(&request);
+        if let Some(auth_mode) = &auth_mode {
+            builder = auth_mode.apply(builder);
         }
+        builder
+    };
+
+    let token = match request_id.as_deref() {
+        Some(id) => http::register(cancellations, id)?,
+        None => tokio_util::sync::CancellationToken::new(),
+    };
+    let app_for_progress = app.clone();
+    let progress_request_id = request_id.clone();
+
+    let response = http::send_with_retry(build_request, &token, |attempt, delay| {
+        if let Some(request_id) = &progress_request_id {
+            let _ = app_for_progress.emit(
+                "chat-retry",
+                serde_json::json!({
+                    "request_id": request_id,
+                    "attempt": attempt,
+                    "delay_ms": delay.as_millis(),
+                }),
+            );
+        }
+    })
+    .await;
+
+    if let Some(request_id) = &request_id {
+        http::unregister(cancellations, request_id)?;
     }
 
-    let response = request_builder
-        .send()
-        .await
-        .map_err(|e| format!("Network error: {}", e))?;
+    let response = response?;
 
     if !response.status().is_success() {
         return Err(format!("API error: HTTP {}", response.status()));
     }
 
-    response
+    let api_response = response
         .json::<ApiResponse>()
         .await
-        .map_err(|e| format!("Failed to parse response: {}", e))
+        .map_err(|e| format!("Failed to parse response: {}", e))?;
+
+    {
+        let mut state = state.lock().map_err(|e| e.to_string())?;
+        state.record_exchange(
+            app,
+            &user_guid,
+            ChatMessage {
+                role: "user".to_string(),
+                content: user_input,
+                timestamp: None,
+            },
+            ChatMessage {
+                role: "assistant".to_string(),
+                content: api_response.assistant_response.clone(),
+                timestamp: None,
+            },
+        )?;
+    }
+
+    Ok(api_response)
 }
 
 /// Test endpoint connectivity
 #[tauri::command]
-async fn test_endpoint(endpoint_url: String, api_key: Option<String>) -> Result<bool, String> {
+async fn test_endpoint(
+    app: AppHandle,
+    endpoint_url: String,
+    auth_mode: Option<AuthMode>,
+    secret_ref: Option<String>,
+) -> Result<bool, String> {
     let client = reqwest::Client::builder()
         .timeout(std::time::Duration::from_secs(5))
         .build()
@@ -114,10 +209,9 @@ async fn test_endpoint(endpoint_url: String, api_key: Option<String>) -> Result<
         .header("Content-Type", "application/json")
         .json(&request);
 
-    if let Some(key) = api_key {
-        if !key.is_empty() {
-            request_builder = request_builder.header("x-functions-key", key);
-        }
+    let auth_mode = resolve_auth_mode(&app, auth_mode, secret_ref)?;
+    if let Some(auth_mode) = auth_mode {
+        request_builder = auth_mode.apply(request_builder);
     }
 
     let response = request_builder.send().await.map_err(|e| e.to_string())?;
@@ -125,6 +219,46 @@ async fn test_endpoint(endpoint_url: String, api_key: Option<String>) -> Result<
     Ok(response.status().is_success())
 }
 
+/// Resolve the credential an `AuthMode` should carry: if a `secret_ref` is
+/// given, decrypt it just-in-time and substitute it in, so the plaintext
+/// never has to sit in session state or cross the JS boundary. Used by
+/// `test_endpoint`, which takes an explicit, never-persisted `AuthMode` for
+/// ad hoc connectivity checks.
+fn resolve_auth_mode(
+    app: &AppHandle,
+    auth_mode: Option<AuthMode>,
+    secret_ref: Option<String>,
+) -> Result<Option<AuthMode>, String> {
+    let Some(secret_ref) = secret_ref else {
+        return Ok(auth_mode);
+    };
+
+    let secret_value = secrets::load_secret_value(app, &secret_ref)?;
+    Ok(Some(match auth_mode {
+        Some(AuthMode::BearerToken { .. }) => AuthMode::BearerToken {
+            access_token: secret_value,
+        },
+        _ => AuthMode::FunctionKey { key: secret_value },
+    }))
+}
+
+/// Resolve a managed session's credential from its `auth_scheme` and
+/// `secret_ref`. Unlike `resolve_auth_mode`, the session never holds a
+/// literal credential to fall back to: without both an `auth_scheme` and a
+/// `secret_ref` there is no credential to resolve.
+pub(crate) fn resolve_session_auth(
+    app: &AppHandle,
+    auth_scheme: Option<AuthScheme>,
+    secret_ref: Option<String>,
+) -> Result<Option<AuthMode>, String> {
+    let (Some(auth_scheme), Some(secret_ref)) = (auth_scheme, secret_ref) else {
+        return Ok(None);
+    };
+
+    let secret_value = secrets::load_secret_value(app, &secret_ref)?;
+    Ok(Some(auth_scheme.resolve(secret_value)))
+}
+
 /// Get system information
 #[tauri::command]
 fn get_system_info() -> Result<serde_json::Value, String> {
@@ -149,7 +283,7 @@ async fn show_notification(app: AppHandle, title: String, body: String) -> Resul
 }
 
 fn main() {
-    tauri::Builder::default()
+    let app = tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_dialog::init())
@@ -163,7 +297,19 @@ fn main() {
             export_data,
             import_data,
             send_chat_message,
+            streaming::send_chat_message_stream,
             test_endpoint,
+            auth::acquire_access_token,
+            state::new_session,
+            state::append_message,
+            state::get_session,
+            state::clear_session,
+            secrets::store_secret,
+            secrets::load_secret,
+            secrets::delete_secret,
+            updater::check_for_update,
+            updater::install_update,
+            http::cancel_request,
             get_system_info,
             show_notification,
         ])
@@ -173,6 +319,22 @@ fn main() {
                 let _ = fs::create_dir_all(&app_data_dir);
             }
 
+            // Restore any sessions persisted from a previous run
+            let sessions = AppState::load(&app.handle());
+            app.manage(Mutex::new(sessions));
+
+            // Shared pooled client and cancellation registry for all
+            // outbound chat traffic
+            app.manage(http::build_client());
+            app.manage(CancellationRegistry::default());
+
+            // Let the companion entra_cli binary drive the running app. Not
+            // yet available on every platform (see ipc::start), so a
+            // failure here is logged rather than aborting startup.
+            if let Err(e) = ipc::start(app.handle().clone()) {
+                eprintln!("entra_cli IPC server did not start: {}", e);
+            }
+
             // Set up any additional initialization here
             #[cfg(debug_assertions)]
             {
@@ -182,6 +344,12 @@ fn main() {
 
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application");
+
+    app.run(|app_handle, event| {
+        if let tauri::RunEvent::ExitRequested { .. } = event {
+            ipc::cleanup(app_handle);
+        }
+    });
 }
@@ -0,0 +1,126 @@
+use aes_gcm::aead::Aead;
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+use base64::Engine;
+use rand::RngCore;
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+const SERVICE: &str = "com.kody-w.entra-copilot-agent";
+const KEYRING_ENTRY: &str = "secrets-master-key";
+
+fn secrets_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("secrets");
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir)
+}
+
+/// Secret names become filenames, so restrict them to a safe charset
+/// rather than trusting the caller-supplied string verbatim (no `.`, `/`,
+/// or other path-traversal characters).
+fn validate_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid secret name '{}': only letters, digits, '-' and '_' are allowed",
+            name
+        ))
+    }
+}
+
+fn secret_path(app: &AppHandle, name: &str) -> Result<PathBuf, String> {
+    validate_name(name)?;
+    Ok(secrets_dir(app)?.join(format!("{}.enc", name)))
+}
+
+/// Fetch the master encryption key from the OS keychain, generating and
+/// storing one on first use.
+fn master_key() -> Result<Aes256Gcm, String> {
+    let entry = keyring::Entry::new(SERVICE, KEYRING_ENTRY).map_err(|e| e.to_string())?;
+
+    let key_b64 = match entry.get_password() {
+        Ok(existing) => existing,
+        Err(keyring::Error::NoEntry) => {
+            let mut key_bytes = [0u8; 32];
+            rand::thread_rng().fill_bytes(&mut key_bytes);
+            let encoded = base64::engine::general_purpose::STANDARD.encode(key_bytes);
+            entry
+                .set_password(&encoded)
+                .map_err(|e| format!("Failed to store master key: {}", e))?;
+            encoded
+        }
+        Err(e) => return Err(format!("Failed to read master key: {}", e)),
+    };
+
+    let key_bytes = base64::engine::general_purpose::STANDARD
+        .decode(key_b64)
+        .map_err(|e| e.to_string())?;
+    Aes256Gcm::new_from_slice(&key_bytes).map_err(|e| e.to_string())
+}
+
+/// Encrypt `value` with the keychain-derived key and write it under the
+/// app-data dir, keyed by `name`. The plaintext never touches disk.
+#[tauri::command]
+pub(crate) fn store_secret(app: AppHandle, name: String, value: String) -> Result<(), String> {
+    let cipher = master_key()?;
+
+    let mut nonce_bytes = [0u8; 12];
+    rand::thread_rng().fill_bytes(&mut nonce_bytes);
+    let nonce = Nonce::from_slice(&nonce_bytes);
+
+    let ciphertext = cipher
+        .encrypt(nonce, value.as_bytes())
+        .map_err(|e| format!("Failed to encrypt secret: {}", e))?;
+
+    let mut payload = nonce_bytes.to_vec();
+    payload.extend(ciphertext);
+
+    fs::write(secret_path(&app, &name)?, payload)
+        .map_err(|e| format!("Failed to write secret '{}': {}", name, e))
+}
+
+/// Decrypt and return a previously stored secret.
+#[tauri::command]
+pub(crate) fn load_secret(app: AppHandle, name: String) -> Result<String, String> {
+    load_secret_value(&app, &name)
+}
+
+/// Non-command helper so other commands can resolve a `secret_ref` inline
+/// without going through the IPC boundary.
+pub(crate) fn load_secret_value(app: &AppHandle, name: &str) -> Result<String, String> {
+    let payload = fs::read(secret_path(app, name)?)
+        .map_err(|e| format!("Failed to read secret '{}': {}", name, e))?;
+
+    if payload.len() < 12 {
+        return Err(format!("Corrupt secret file for '{}'", name));
+    }
+    let (nonce_bytes, ciphertext) = payload.split_at(12);
+    let nonce = Nonce::from_slice(nonce_bytes);
+
+    let cipher = master_key()?;
+    let plaintext = cipher
+        .decrypt(nonce, ciphertext)
+        .map_err(|e| format!("Failed to decrypt secret '{}': {}", name, e))?;
+
+    String::from_utf8(plaintext).map_err(|e| e.to_string())
+}
+
+/// Remove a stored secret.
+#[tauri::command]
+pub(crate) fn delete_secret(app: AppHandle, name: String) -> Result<(), String> {
+    let path = secret_path(&app, &name)?;
+    if path.exists() {
+        fs::remove_file(path).map_err(|e| format!("Failed to delete secret '{}': {}", name, e))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,133 @@
+use crate::auth::AuthScheme;
+use crate::ChatMessage;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, State};
+
+/// One active conversation: where it talks to and what's been said so far.
+/// Credentials are never stored inline — only a `secret_ref` naming an entry
+/// in the encrypted secret store, so `sessions.json` never holds a literal
+/// key or token.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Session {
+    pub(crate) endpoint_url: String,
+    pub(crate) auth_scheme: Option<AuthScheme>,
+    pub(crate) secret_ref: Option<String>,
+    pub(crate) messages: Vec<ChatMessage>,
+}
+
+/// Active sessions keyed by `user_guid`, managed behind a mutex and
+/// persisted to the app-data dir on every mutation.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct AppState {
+    sessions: HashMap<String, Session>,
+}
+
+pub(crate) type ManagedState = Mutex<AppState>;
+
+impl AppState {
+    fn sessions_file(app: &AppHandle) -> Result<PathBuf, String> {
+        let dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+        Ok(dir.join("sessions.json"))
+    }
+
+    /// Load persisted sessions from the app-data dir, if any exist.
+    pub(crate) fn load(app: &AppHandle) -> AppState {
+        Self::sessions_file(app)
+            .ok()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .unwrap_or_default()
+    }
+
+    fn persist(&self, app: &AppHandle) -> Result<(), String> {
+        let path = Self::sessions_file(app)?;
+        let data = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(path, data).map_err(|e| format!("Failed to persist sessions: {}", e))
+    }
+
+    pub(crate) fn get(&self, user_guid: &str) -> Option<Session> {
+        self.sessions.get(user_guid).cloned()
+    }
+
+    /// Append the user/assistant turn produced by a completed chat call and
+    /// persist the updated session.
+    pub(crate) fn record_exchange(
+        &mut self,
+        app: &AppHandle,
+        user_guid: &str,
+        user_message: ChatMessage,
+        assistant_message: ChatMessage,
+    ) -> Result<(), String> {
+        if let Some(session) = self.sessions.get_mut(user_guid) {
+            session.messages.push(user_message);
+            session.messages.push(assistant_message);
+        }
+        self.persist(app)
+    }
+}
+
+/// Start (or reset) a managed session for `user_guid`.
+#[tauri::command]
+pub(crate) fn new_session(
+    app: AppHandle,
+    state: State<'_, ManagedState>,
+    user_guid: String,
+    endpoint_url: String,
+    auth_scheme: Option<AuthScheme>,
+    secret_ref: Option<String>,
+) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    state.sessions.insert(
+        user_guid,
+        Session {
+            endpoint_url,
+            auth_scheme,
+            secret_ref,
+            messages: Vec::new(),
+        },
+    );
+    state.persist(&app)
+}
+
+/// Append a message to an existing session's history.
+#[tauri::command]
+pub(crate) fn append_message(
+    app: AppHandle,
+    state: State<'_, ManagedState>,
+    user_guid: String,
+    message: ChatMessage,
+) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    let session = state
+        .sessions
+        .get_mut(&user_guid)
+        .ok_or_else(|| format!("No session for {}", user_guid))?;
+    session.messages.push(message);
+    state.persist(&app)
+}
+
+/// Fetch a session's current state, if it exists.
+#[tauri::command]
+pub(crate) fn get_session(
+    state: State<'_, ManagedState>,
+    user_guid: String,
+) -> Result<Option<Session>, String> {
+    let state = state.lock().map_err(|e| e.to_string())?;
+    Ok(state.sessions.get(&user_guid).cloned())
+}
+
+/// Drop a session entirely, e.g. when the user starts a fresh conversation.
+#[tauri::command]
+pub(crate) fn clear_session(
+    app: AppHandle,
+    state: State<'_, ManagedState>,
+    user_guid: String,
+) -> Result<(), String> {
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    state.sessions.remove(&user_guid);
+    state.persist(&app)
+}
@@ -0,0 +1,184 @@
+use crate::http::{CancellationRegistry, HttpClientState};
+use crate::state::ManagedState;
+use crate::{resolve_session_auth, ApiRequest, ChatMessage};
+use eventsource_stream::Eventsource;
+use futures::TryStreamExt;
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// Request a Server-Sent Events response from the chat endpoint and stream
+/// incremental tokens to the webview as `chat-stream-chunk` events, keyed by
+/// `request_id` so multiple turns can be in flight at once. Reads the
+/// session's endpoint/history and resolves auth from its `secret_ref` the
+/// same way `send_chat_message_core` does, so the frontend never has to
+/// hand a decrypted secret back over IPC, and records the completed turn
+/// once the stream finishes so it isn't invisible to the managed session.
+/// The same `request_id` can be passed to `cancel_request` to abort
+/// mid-stream, which emits `chat-stream-cancelled` instead of
+/// `chat-stream-done` so the frontend can tell the two apart.
+#[tauri::command]
+pub async fn send_chat_message_stream(
+    app: AppHandle,
+    state: State<'_, ManagedState>,
+    client: State<'_, HttpClientState>,
+    cancellations: State<'_, CancellationRegistry>,
+    user_guid: String,
+    user_input: String,
+    request_id: String,
+) -> Result<(), String> {
+    let session = {
+        let state = state.lock().map_err(|e| e.to_string())?;
+        state
+            .get(&user_guid)
+            .ok_or_else(|| format!("No session for {}", user_guid))?
+    };
+
+    let request = ApiRequest {
+        user_input: user_input.clone(),
+        conversation_history: session.messages,
+        user_guid: Some(user_guid.clone()),
+    };
+
+    let auth_mode = resolve_session_auth(&app, session.auth_scheme, session.secret_ref)?;
+    let endpoint_url = session.endpoint_url;
+
+    let cancellation = crate::http::register(&cancellations, &request_id)?;
+
+    let mut request_builder = client
+        .post(&endpoint_url)
+        .header("Content-Type", "application/json")
+        .header("Accept", "text/event-stream")
+        .json(&request);
+
+    if let Some(auth_mode) = &auth_mode {
+        request_builder = auth_mode.apply(request_builder);
+    }
+
+    let send_result = tokio::select! {
+        _ = cancellation.cancelled() => {
+            crate::http::unregister(&cancellations, &request_id)?;
+            return Err("Request cancelled".to_string());
+        }
+        result = request_builder.send() => result,
+    };
+
+    let response = match send_result {
+        Ok(response) => response,
+        Err(e) => {
+            crate::http::unregister(&cancellations, &request_id)?;
+            return Err(format!("Network error: {}", e));
+        }
+    };
+
+    if !response.status().is_success() {
+        crate::http::unregister(&cancellations, &request_id)?;
+        return Err(format!("API error: HTTP {}", response.status()));
+    }
+
+    tauri::async_runtime::spawn(async move {
+        let mut stream = response.bytes_stream().eventsource();
+        let mut assistant_response = String::new();
+        let mut cancelled = false;
+
+        loop {
+            let next = tokio::select! {
+                _ = cancellation.cancelled() => {
+                    cancelled = true;
+                    break;
+                }
+                next = stream.try_next() => next,
+            };
+
+            match next {
+                Ok(Some(event)) => {
+                    if event.data == "[DONE]" {
+                        break;
+                    }
+
+                    match serde_json::from_str::<Value>(&event.data) {
+                        Ok(json) => {
+                            if let Some(content) = json.get("content").and_then(Value::as_str) {
+                                assistant_response.push_str(content);
+                                let _ = app.emit(
+                                    "chat-stream-chunk",
+                                    serde_json::json!({
+                                        "request_id": request_id,
+                                        "content": content,
+                                    }),
+                                );
+                            }
+                        }
+                        Err(e) => {
+                            let _ = app.emit(
+                                "chat-stream-error",
+                                serde_json::json!({
+                                    "request_id": request_id,
+                                    "message": format!("Failed to parse stream event: {}", e),
+                                }),
+                            );
+                        }
+                    }
+                }
+                Ok(None) => break,
+                Err(e) => {
+                    let _ = app.emit(
+                        "chat-stream-error",
+                        serde_json::json!({
+                            "request_id": request_id,
+                            "message": format!("Stream error: {}", e),
+                        }),
+                    );
+                    let _ = crate::http::unregister(&app.state::<CancellationRegistry>(), &request_id);
+                    return;
+                }
+            }
+        }
+
+        if cancelled {
+            let _ = app.emit(
+                "chat-stream-cancelled",
+                serde_json::json!({ "request_id": request_id }),
+            );
+        } else {
+            let record_result: Result<(), String> = app
+                .state::<ManagedState>()
+                .lock()
+                .map_err(|e| e.to_string())
+                .and_then(|mut guard| {
+                    guard.record_exchange(
+                        &app,
+                        &user_guid,
+                        ChatMessage {
+                            role: "user".to_string(),
+                            content: user_input,
+                            timestamp: None,
+                        },
+                        ChatMessage {
+                            role: "assistant".to_string(),
+                            content: assistant_response,
+                            timestamp: None,
+                        },
+                    )
+                });
+
+            if let Err(e) = record_result {
+                let _ = app.emit(
+                    "chat-stream-error",
+                    serde_json::json!({
+                        "request_id": request_id,
+                        "message": format!("Failed to persist streamed exchange: {}", e),
+                    }),
+                );
+            }
+
+            let _ = app.emit(
+                "chat-stream-done",
+                serde_json::json!({ "request_id": request_id }),
+            );
+        }
+
+        let _ = crate::http::unregister(&app.state::<CancellationRegistry>(), &request_id);
+    });
+
+    Ok(())
+}
@@ -0,0 +1,124 @@
+use base64::Engine;
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_shell::ShellExt;
+
+/// Public half of the release-signing keypair, pinned into the binary. Only
+/// an artifact signed by the matching private key (held by the release
+/// pipeline, never shipped) verifies, so a compromised manifest host or a
+/// MITM of `download_url` can't forge an update just by hashing the payload.
+const RELEASE_PUBLIC_KEY: [u8; 32] = [
+    0x1f, 0x3a, 0x9c, 0x4e, 0x72, 0xb8, 0x05, 0xd1, 0x6a, 0xc3, 0x8e, 0x44, 0x29, 0xf7, 0x91, 0x5d,
+    0x83, 0x1b, 0x6f, 0x0a, 0x52, 0xe9, 0x37, 0xc6, 0x08, 0xad, 0x74, 0x9b, 0x2e, 0xf1, 0x63, 0xda,
+];
+
+#[derive(Debug, Deserialize)]
+struct VersionManifest {
+    version: String,
+    notes: String,
+    download_url: String,
+    signature: String,
+}
+
+/// Result of comparing a fetched release manifest against the running
+/// build, surfaced to the frontend to drive the update dialog.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct UpdateInfo {
+    current_version: String,
+    latest_version: String,
+    notes: String,
+    download_url: String,
+    signature: String,
+    update_available: bool,
+}
+
+/// Fetch the signed release manifest from `manifest_url` and compare it to
+/// `CARGO_PKG_VERSION`. Emits `tauri://update-available` when a newer build
+/// is published so enterprise deployments can host their own release feed.
+#[tauri::command]
+pub(crate) async fn check_for_update(
+    app: AppHandle,
+    manifest_url: String,
+) -> Result<UpdateInfo, String> {
+    let manifest = reqwest::Client::new()
+        .get(&manifest_url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch update manifest: {}", e))?
+        .json::<VersionManifest>()
+        .await
+        .map_err(|e| format!("Failed to parse update manifest: {}", e))?;
+
+    let current_version = env!("CARGO_PKG_VERSION").to_string();
+    let update_available = is_newer(&manifest.version, &current_version);
+
+    let info = UpdateInfo {
+        current_version,
+        latest_version: manifest.version,
+        notes: manifest.notes,
+        download_url: manifest.download_url,
+        signature: manifest.signature,
+        update_available,
+    };
+
+    if info.update_available {
+        let _ = app.emit("tauri://update-available", &info);
+    }
+
+    Ok(info)
+}
+
+/// Download the update artifact named in a manifest, verify it against the
+/// manifest's signature, and hand the staged file off to the OS installer.
+#[tauri::command]
+pub(crate) async fn install_update(
+    app: AppHandle,
+    download_url: String,
+    signature: String,
+) -> Result<(), String> {
+    let bytes = reqwest::get(&download_url)
+        .await
+        .map_err(|e| format!("Failed to download update: {}", e))?
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to read update artifact: {}", e))?;
+
+    verify_signature(&bytes, &signature)?;
+
+    let installer_path = std::env::temp_dir().join("entra-copilot-agent-update.bin");
+    std::fs::write(&installer_path, &bytes)
+        .map_err(|e| format!("Failed to stage update artifact: {}", e))?;
+
+    app.shell()
+        .open(installer_path.to_string_lossy(), None)
+        .map_err(|e| format!("Failed to launch installer: {}", e))
+}
+
+/// Verify `bytes` against a base64-encoded ed25519 signature, checked with
+/// the public key pinned above rather than anything sourced from the
+/// manifest itself.
+fn verify_signature(bytes: &[u8], signature_b64: &str) -> Result<(), String> {
+    let verifying_key = VerifyingKey::from_bytes(&RELEASE_PUBLIC_KEY)
+        .map_err(|e| format!("Invalid pinned release public key: {}", e))?;
+
+    let signature_bytes = base64::engine::general_purpose::STANDARD
+        .decode(signature_b64)
+        .map_err(|e| format!("Malformed signature: {}", e))?;
+    let signature_bytes: [u8; 64] = signature_bytes
+        .try_into()
+        .map_err(|_| "Signature must be 64 bytes".to_string())?;
+    let signature = Signature::from_bytes(&signature_bytes);
+
+    verifying_key
+        .verify_strict(bytes, &signature)
+        .map_err(|_| "Update artifact failed signature verification".to_string())
+}
+
+/// Compare two `major.minor.patch`-style version strings numerically.
+fn is_newer(candidate: &str, current: &str) -> bool {
+    fn parts(v: &str) -> Vec<u64> {
+        v.split('.').filter_map(|p| p.parse().ok()).collect()
+    }
+    parts(candidate) > parts(current)
+}